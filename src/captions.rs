@@ -19,6 +19,20 @@ mod srt;
 use srt::*;
 mod beast_captions;
 use beast_captions::*;
+mod vtt;
+use vtt::*;
+mod scc;
+use scc::*;
+mod stream;
+mod burn;
+mod media;
+mod cache;
+mod markdown;
+
+/// Default `--max-chars-per-line` when only `--max-lines` is given.
+const DEFAULT_MAX_CHARS_PER_LINE: usize = 42;
+/// Default `--max-lines` when only `--max-chars-per-line` is given.
+const DEFAULT_MAX_LINES: usize = 2;
 
 #[derive(Args, Debug)]
 pub struct Caption {
@@ -37,11 +51,14 @@ pub struct Caption {
     /// exists
     #[clap(short, long, value_parser)]
     output_path: Option<Utf8PathBuf>,
-    /// output the raw deepgram response
-    /// as Rust structs.
+    /// output the reduced, cacheable view of the deepgram response as
+    /// Rust structs.
     ///
-    /// Deepgram doesn't supply Serialize for the
-    /// Response type.
+    /// This is `CachedResponse` (transcript words and utterances), not
+    /// Deepgram's full `Response` -- Deepgram doesn't supply `Serialize`
+    /// for `Response`, so only the subset lamarck persists to the cache
+    /// is available to dump here. Confidence scores, metadata, detected
+    /// language, and paragraphs are not included.
     #[clap(
         short,
         long,
@@ -57,6 +74,18 @@ pub struct Caption {
         help_heading = "OUTPUT_TYPE"
     )]
     srt: bool,
+    /// wrap SRT cue text to at most this many characters per line,
+    /// splitting overlong cues into multiple consecutive ones once they
+    /// exceed `--max-lines` (default 42, the common subtitling guideline,
+    /// when only `--max-lines` is given; with neither option set, cues are
+    /// not wrapped)
+    #[clap(long, help_heading = "SRT")]
+    max_chars_per_line: Option<usize>,
+    /// maximum lines per SRT cue before it's split into multiple
+    /// consecutive cues, with timing divided proportionally by word count
+    /// (default 2 when only `--max-chars-per-line` is given)
+    #[clap(long, help_heading = "SRT")]
+    max_lines: Option<usize>,
     /// output an srt file that contains single-words
     /// like you would find in burn-in captions from
     /// mrbeast or similar
@@ -67,6 +96,17 @@ pub struct Caption {
         help_heading = "OUTPUT_TYPE"
     )]
     beast_captions: bool,
+    /// output a WebVTT file, for `<track>` elements in web video players
+    #[clap(
+        short,
+        long,
+        default_value_t = false,
+        help_heading = "OUTPUT_TYPE"
+    )]
+    vtt: bool,
+    /// output a CEA-608 Scenarist Closed Captions (.scc) file
+    #[clap(long, default_value_t = false, help_heading = "OUTPUT_TYPE")]
+    scc: bool,
     /// output a transcript
     #[clap(
         short,
@@ -75,12 +115,89 @@ pub struct Caption {
         help_heading = "OUTPUT_TYPE"
     )]
     transcript: bool,
-    /// output a markdown file with links to video
-    /// timestamps
-    #[clap(short, long, help_heading = "OUTPUT_TYPE")]
-    lang: Option<String>,
+    /// the language to transcribe in, e.g. `en`, `en_us`, `de`, `fr_ca`
     #[clap(short, long, help_heading = "Language")]
+    lang: Option<String>,
+    /// output a markdown transcript, chaptered by utterance and prefixed
+    /// with `[MM:SS]` timestamps. When `input` is a YouTube watch URL,
+    /// each timestamp deep-links to that moment in the video.
+    #[clap(short, long, help_heading = "OUTPUT_TYPE")]
     markdown: bool,
+    /// stream audio to Deepgram's live transcription endpoint instead of
+    /// sending a finished recording
+    ///
+    /// Reads raw linear16/PCM audio from `input` (a file path, or `-` for
+    /// stdin) through to EOF and writes finalized cues to the SRT output
+    /// as they arrive. Does not tail a growing file or a live microphone
+    /// device; the read stops at the first EOF.
+    #[clap(long, help_heading = "STREAMING")]
+    stream: bool,
+    /// sample rate (Hz) of the raw linear16/PCM audio passed to `--stream`
+    #[clap(
+        long,
+        help_heading = "STREAMING",
+        default_value_t = 16000
+    )]
+    stream_sample_rate: u32,
+    /// channel count of the raw linear16/PCM audio passed to `--stream`
+    #[clap(long, help_heading = "STREAMING", default_value_t = 1)]
+    stream_channels: u32,
+    /// burn the generated captions into the source video and mux out an
+    /// mp4, instead of leaving the viewer to composite them by hand
+    ///
+    /// requires `ffmpeg` on PATH and a video file as input
+    #[clap(long, help_heading = "OUTPUT_TYPE")]
+    burn: bool,
+    /// font size (in points) for burned-in captions
+    #[clap(long, help_heading = "BURN", default_value_t = 48)]
+    burn_font_size: u32,
+    /// where burned-in captions are anchored on the frame
+    #[clap(
+        long,
+        help_heading = "BURN",
+        value_enum,
+        default_value_t = BurnPosition::Center
+    )]
+    burn_position: BurnPosition,
+    /// draw a black outline around burned-in caption text
+    #[clap(
+        long,
+        help_heading = "BURN",
+        action = clap::ArgAction::Set,
+        default_value_t = true
+    )]
+    burn_outline: bool,
+    /// highlight color for burned-in caption text, as an `&HBBGGRR` ASS
+    /// color or a `#RRGGBB` hex string
+    #[clap(long, help_heading = "BURN", default_value = "#FFFFFF")]
+    burn_highlight_color: String,
+    /// don't read from or write to the on-disk response cache
+    #[clap(long, help_heading = "CACHE")]
+    no_cache: bool,
+    /// ignore any cached response for this input and re-transcribe,
+    /// refreshing the cache entry
+    #[clap(long, help_heading = "CACHE")]
+    refresh: bool,
+}
+
+/// Where burned-in captions sit on the frame, mirroring libass/SSA
+/// numpad alignment positions.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum BurnPosition {
+    Top,
+    Center,
+    Bottom,
+}
+
+impl BurnPosition {
+    /// The libass `Alignment` override-tag value for this position.
+    fn ass_alignment(self) -> u8 {
+        match self {
+            BurnPosition::Bottom => 2,
+            BurnPosition::Center => 5,
+            BurnPosition::Top => 8,
+        }
+    }
 }
 
 #[derive(Error, Diagnostic, Debug)]
@@ -118,6 +235,28 @@ pub enum CaptionError {
       )]
     #[diagnostic(code(lamarck::mime_not_audio))]
     InvalidMimeType { guess: mime_guess::Mime },
+
+    #[error("Deepgram's live transcription WebSocket errored")]
+    #[diagnostic(code(lamarck::stream_error))]
+    StreamError(#[from] tokio_tungstenite::tungstenite::Error),
+
+    #[error("Couldn't set up the live transcription request: {message}")]
+    #[diagnostic(code(lamarck::stream_setup_error))]
+    StreamSetupError { message: String },
+
+    #[error("ffmpeg exited with {status}: {stderr}")]
+    #[diagnostic(code(lamarck::ffmpeg_error))]
+    FfmpegError { status: std::process::ExitStatus, stderr: String },
+
+    #[error(
+        "Couldn't find an audio stream in {filepath}. Deepgram requires an audio track."
+    )]
+    #[diagnostic(code(lamarck::no_audio_stream))]
+    NoAudioStreamError { filepath: Utf8PathBuf },
+
+    #[error("Couldn't write the response cache: {message}")]
+    #[diagnostic(code(lamarck::cache_error))]
+    CacheError { message: String },
 }
 
 impl From<DeepgramError> for CaptionError {
@@ -129,6 +268,10 @@ impl From<DeepgramError> for CaptionError {
 pub async fn generate_captions(
     options: &Caption,
 ) -> Result<(), CaptionError> {
+    if options.stream {
+        return generate_live_captions(options).await;
+    }
+
     let bar = ProgressBar::new(1);
 
     bar.set_style(ProgressStyle::default_bar()
@@ -175,36 +318,6 @@ pub async fn generate_captions(
         });
     }
 
-    let source = match Url::parse(&options.input) {
-        Ok(_) => Ok(AudioSource::from_url(&options.input)),
-        Err(url_error) => {
-            debug!("url failed to parse {:?}", url_error);
-            let filepath =
-                Utf8PathBuf::from(&options.input);
-            let file = File::open(&filepath).await.unwrap();
-
-            match mime_guess::from_path(&options.input)
-                .first()
-            {
-                Some(guess) => {
-                    if guess.type_() != "audio" {
-                        Err(CaptionError::InvalidMimeType {
-                            guess,
-                        })
-                    } else {
-                        Ok(AudioSource::from_buffer_with_mime_type(
-                            file,
-                            guess.to_string(),
-                        ))
-                    }
-                }
-                None => Err(CaptionError::MimeGuessError {
-                    filepath: filepath,
-                }),
-            }
-        }
-    }?;
-
     fn map_string_to_language(string: &str) -> Language {
         match string {
             "zh" => Language::zh,
@@ -242,21 +355,142 @@ pub async fn generate_captions(
         Some(language) => map_string_to_language(language),
         None => Language::en_US,
     };
+    let lang_key =
+        options.lang.clone().unwrap_or_else(|| "en_US".to_string());
+
+    // When the input is a local video file, ffmpeg extracts its audio track
+    // to this temporary WAV file, which is cleaned up once Deepgram has the
+    // response in hand. Extraction is deferred until after the cache lookup
+    // below, so a cache hit skips the transcode entirely.
+    let mut temp_audio_path: Option<Utf8PathBuf> = None;
+
+    /// What to turn into a Deepgram `AudioSource` on a cache miss, decided
+    /// up front so the (possibly expensive) conversion can be skipped on a
+    /// hit.
+    enum PendingSource {
+        Url(String),
+        Video(Utf8PathBuf),
+        Audio(Utf8PathBuf, mime_guess::Mime),
+    }
+
+    let (pending, hash_input) = match Url::parse(&options.input) {
+        Ok(_) => {
+            let hash_input = format!(
+                "url:{}:{lang_key}",
+                options.input
+            )
+            .into_bytes();
+            Ok((PendingSource::Url(options.input.clone()), hash_input))
+        }
+        Err(url_error) => {
+            debug!("url failed to parse {:?}", url_error);
+            let filepath =
+                Utf8PathBuf::from(&options.input);
+
+            match mime_guess::from_path(&options.input)
+                .first()
+            {
+                Some(guess) if guess.type_() == "video" => {
+                    // Hash the source video bytes rather than the
+                    // extracted audio, so a cache hit never has to run
+                    // ffmpeg.
+                    let mut hash_input =
+                        tokio::fs::read(&filepath).await?;
+                    hash_input
+                        .extend_from_slice(lang_key.as_bytes());
+                    Ok((PendingSource::Video(filepath), hash_input))
+                }
+                Some(guess) => {
+                    if guess.type_() != "audio" {
+                        Err(CaptionError::InvalidMimeType {
+                            guess,
+                        })
+                    } else {
+                        let mut hash_input =
+                            tokio::fs::read(&filepath).await?;
+                        hash_input.extend_from_slice(
+                            lang_key.as_bytes(),
+                        );
+                        Ok((
+                            PendingSource::Audio(filepath, guess),
+                            hash_input,
+                        ))
+                    }
+                }
+                None => Err(CaptionError::MimeGuessError {
+                    filepath: filepath,
+                }),
+            }
+        }
+    }?;
+
+    let cache_dir = Utf8PathBuf::from(cache::CACHE_DIR);
+    let cache_key = cache::content_hash(&hash_input);
+
+    let cache_hit = if options.no_cache || options.refresh {
+        None
+    } else {
+        cache::read(&cache_dir, cache_key).await
+    };
+
+    let response = match cache_hit {
+        Some(cached) => {
+            bar.set_message("using cached deepgram response");
+            cached
+        }
+        None => {
+            let source = match pending {
+                PendingSource::Url(url) => {
+                    AudioSource::from_url(&url)
+                }
+                PendingSource::Video(filepath) => {
+                    bar.set_message("extracting audio track");
+                    let audio_path =
+                        media::extract_audio(&filepath).await?;
+                    let file =
+                        File::open(&audio_path).await?;
+                    temp_audio_path = Some(audio_path);
+                    AudioSource::from_buffer_with_mime_type(
+                        file,
+                        "audio/wav".to_string(),
+                    )
+                }
+                PendingSource::Audio(filepath, guess) => {
+                    let file =
+                        File::open(&filepath).await?;
+                    AudioSource::from_buffer_with_mime_type(
+                        file,
+                        guess.to_string(),
+                    )
+                }
+            };
+
+            let dg_client =
+                Deepgram::new(&options.deepgram_api_key);
 
-    let dg_client =
-        Deepgram::new(&options.deepgram_api_key);
+            let deepgram_options = Options::builder()
+                .punctuate(true)
+                .language(language)
+                .utterances(true)
+                .build();
 
-    let deepgram_options = Options::builder()
-        .punctuate(true)
-        .language(language)
-        .utterances(true)
-        .build();
+            bar.set_message("waiting for deepgram");
+            let response = dg_client
+                .transcription()
+                .prerecorded(source, &deepgram_options)
+                .await?;
 
-    bar.set_message("waiting for deepgram");
-    let response = dg_client
-        .transcription()
-        .prerecorded(source, &deepgram_options)
-        .await?;
+            let cached = cache::CachedResponse::from(&response);
+            if !options.no_cache {
+                cache::write(&cache_dir, cache_key, &cached).await?;
+            }
+            cached
+        }
+    };
+
+    if let Some(audio_path) = temp_audio_path {
+        tokio::fs::remove_file(&audio_path).await?;
+    }
 
     bar.set_message("processing deepgram response");
 
@@ -272,9 +506,8 @@ pub async fn generate_captions(
     }
 
     if options.transcript {
-        let transcript = &response.results.channels[0]
-            .alternatives[0]
-            .transcript;
+        let transcript =
+            &response.channels[0].alternatives[0].transcript;
 
         let mut output = output_location.clone();
         output.set_extension("txt");
@@ -286,7 +519,19 @@ pub async fn generate_captions(
     }
 
     if options.srt {
-        let srts = Srt::from(response.clone());
+        let srts = if options.max_chars_per_line.is_some()
+            || options.max_lines.is_some()
+        {
+            wrapped(
+                &response,
+                options
+                    .max_chars_per_line
+                    .unwrap_or(DEFAULT_MAX_CHARS_PER_LINE),
+                options.max_lines.unwrap_or(DEFAULT_MAX_LINES),
+            )
+        } else {
+            Srt::from(&response)
+        };
         for (channel_id, channel) in
             srts.channels.iter().enumerate()
         {
@@ -309,7 +554,7 @@ pub async fn generate_captions(
     }
 
     if options.beast_captions {
-        let srts = BeastCaptions::from(response);
+        let srts = BeastCaptions::from(&response);
         for (channel_id, channel) in
             srts.channels.iter().enumerate()
         {
@@ -331,10 +576,135 @@ pub async fn generate_captions(
         }
     }
 
+    if options.vtt {
+        let vtts = Vtt::from(&response);
+        for (channel_id, channel) in
+            vtts.channels.iter().enumerate()
+        {
+            for (alternative_id, alternative) in
+                channel.iter().enumerate()
+            {
+                let mut output = output_location.clone();
+                let file_stem = output.file_stem().unwrap();
+                let new_file_stem = format!("{file_stem}-channel-{channel_id}-alternative-{alternative_id}");
+                output.set_file_name(new_file_stem);
+                output.set_extension("vtt");
+
+                let mut vtt_file =
+                    File::create(output).await?;
+                vtt_file
+                    .write_all(alternative.as_bytes())
+                    .await?;
+            }
+        }
+    }
+
+    if options.scc {
+        let sccs = Scc::from(&response);
+        for (channel_id, channel) in
+            sccs.channels.iter().enumerate()
+        {
+            for (alternative_id, alternative) in
+                channel.iter().enumerate()
+            {
+                let mut output = output_location.clone();
+                let file_stem = output.file_stem().unwrap();
+                let new_file_stem = format!("{file_stem}-channel-{channel_id}-alternative-{alternative_id}");
+                output.set_file_name(new_file_stem);
+                output.set_extension("scc");
+
+                let mut scc_file =
+                    File::create(output).await?;
+                scc_file
+                    .write_all(alternative.as_bytes())
+                    .await?;
+            }
+        }
+    }
+
+    if options.burn {
+        if Url::parse(&options.input).is_ok() {
+            warn!(
+                "--burn requires a local video file; skipping because input is a URL"
+            );
+        } else {
+            let srts = BeastCaptions::from(&response);
+            if let Some(cues) =
+                srts.channels.first().and_then(|channel| channel.first())
+            {
+                let mut srt_path = output_location.clone();
+                srt_path.set_extension("burn.srt");
+                let mut srt_file = File::create(&srt_path).await?;
+                srt_file.write_all(cues.as_bytes()).await?;
+
+                let mut mp4_output = output_location.clone();
+                mp4_output.set_extension("mp4");
+
+                let style = burn::BurnStyle {
+                    font_size: options.burn_font_size,
+                    position: options.burn_position,
+                    outline: options.burn_outline,
+                    highlight_color: options.burn_highlight_color.clone(),
+                };
+
+                burn::burn_captions(
+                    &Utf8PathBuf::from(&options.input),
+                    &srt_path,
+                    &style,
+                    &mp4_output,
+                )
+                .await?;
+
+                tokio::fs::remove_file(&srt_path).await?;
+            }
+        }
+    }
+
     if options.markdown {
-        warn!("markdown output is not yet implemented");
+        let transcript =
+            markdown::render_markdown(&response, &options.input);
+
+        let mut output = output_location.clone();
+        output.set_extension("md");
+        let mut markdown_file = File::create(output).await?;
+        markdown_file
+            .write_all(transcript.as_bytes())
+            .await?;
     }
 
     bar.finish_with_message("created caption files");
     Ok(())
 }
+
+/// Live-transcribes audio read from `options.input` against Deepgram's
+/// streaming endpoint, writing cues to the SRT output as they finalize.
+async fn generate_live_captions(
+    options: &Caption,
+) -> Result<(), CaptionError> {
+    let output_location = options
+        .output_path
+        .clone()
+        .unwrap_or(Utf8PathBuf::from("transcript.srt"));
+
+    if options.input == "-" {
+        let mut stdin = tokio::io::stdin();
+        stream::stream_captions(
+            &options.deepgram_api_key,
+            &mut stdin,
+            &output_location,
+            options.stream_sample_rate,
+            options.stream_channels,
+        )
+        .await
+    } else {
+        let mut file = File::open(&options.input).await?;
+        stream::stream_captions(
+            &options.deepgram_api_key,
+            &mut file,
+            &output_location,
+            options.stream_sample_rate,
+            options.stream_channels,
+        )
+        .await
+    }
+}
@@ -0,0 +1,40 @@
+use super::cache::{CachedAlternative, CachedResponse};
+use super::srt::format_timestamp;
+
+/// Per-channel, per-alternative single-word SRT transcripts, styled after
+/// the word-by-word burn-in captions popularised by MrBeast-style shorts.
+pub struct BeastCaptions {
+    pub channels: Vec<Vec<String>>,
+}
+
+impl From<&CachedResponse> for BeastCaptions {
+    fn from(response: &CachedResponse) -> Self {
+        let channels = response
+            .channels
+            .iter()
+            .map(|channel| {
+                channel
+                    .alternatives
+                    .iter()
+                    .map(alternative_to_beast_srt)
+                    .collect()
+            })
+            .collect();
+        BeastCaptions { channels }
+    }
+}
+
+fn alternative_to_beast_srt(alternative: &CachedAlternative) -> String {
+    let mut out = String::new();
+    for (index, word) in alternative.words.iter().enumerate() {
+        let text = word.punctuated_word.as_deref().unwrap_or(&word.word);
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(word.start),
+            format_timestamp(word.end),
+            text
+        ));
+    }
+    out
+}
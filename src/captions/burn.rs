@@ -0,0 +1,87 @@
+use camino::Utf8Path;
+use tokio::process::Command;
+use tracing::*;
+
+use super::{BurnPosition, CaptionError};
+
+/// Styling applied to burned-in captions via libass `force_style` overrides.
+pub struct BurnStyle {
+    pub font_size: u32,
+    pub position: BurnPosition,
+    pub outline: bool,
+    pub highlight_color: String,
+}
+
+/// Hard-renders `srt_path` onto `input_video` using ffmpeg's `subtitles`
+/// (libass) filter and muxes the result out as an mp4 at `output_path`.
+pub async fn burn_captions(
+    input_video: &Utf8Path,
+    srt_path: &Utf8Path,
+    style: &BurnStyle,
+    output_path: &Utf8Path,
+) -> Result<(), CaptionError> {
+    let filter = format!(
+        "subtitles={}:force_style='{}'",
+        escape_for_filter(srt_path),
+        force_style(style),
+    );
+
+    debug!("running ffmpeg with filter: {filter}");
+
+    let output = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-i")
+        .arg(input_video.as_str())
+        .arg("-vf")
+        .arg(filter)
+        .arg("-c:a")
+        .arg("copy")
+        .arg(output_path.as_str())
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(CaptionError::FfmpegError {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr)
+                .into_owned(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Builds the libass `force_style` override string for a [`BurnStyle`].
+fn force_style(style: &BurnStyle) -> String {
+    let outline = if style.outline { 2 } else { 0 };
+    format!(
+        "FontSize={},Alignment={},Outline={},PrimaryColour={}",
+        style.font_size,
+        style.position.ass_alignment(),
+        outline,
+        to_ass_color(&style.highlight_color),
+    )
+}
+
+/// Converts a `#RRGGBB` hex color to libass's `&HBBGGRR&` format. Strings
+/// that already look like an ASS color (start with `&H`) are passed through.
+fn to_ass_color(color: &str) -> String {
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() == 6 {
+            let rr = &hex[0..2];
+            let gg = &hex[2..4];
+            let bb = &hex[4..6];
+            return format!("&H{bb}{gg}{rr}&");
+        }
+    }
+    color.to_string()
+}
+
+/// Escapes a path for use inside ffmpeg's `subtitles=` filter argument,
+/// where `:` and `'` are filtergraph syntax and must be escaped.
+fn escape_for_filter(path: &Utf8Path) -> String {
+    path.as_str()
+        .replace('\\', "\\\\")
+        .replace(':', "\\:")
+        .replace('\'', "\\'")
+}
@@ -0,0 +1,153 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use deepgram::transcription::prerecorded::response::Response as DeepgramResponse;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tracing::*;
+
+use super::CaptionError;
+
+/// Directory cache entries are written under, relative to the current
+/// working directory.
+pub const CACHE_DIR: &str = "lamarck_cache";
+
+/// A serializable mirror of the subset of Deepgram's `Response` that
+/// lamarck actually reads. Deepgram's own `Response` type only implements
+/// `Deserialize`, not `Serialize` (see the `--raw` doc comment), so this
+/// is what gets persisted to and read back from the on-disk cache.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedResponse {
+    pub channels: Vec<CachedChannel>,
+    pub utterances: Vec<CachedUtterance>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedChannel {
+    pub alternatives: Vec<CachedAlternative>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedAlternative {
+    pub transcript: String,
+    pub words: Vec<CachedWord>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedWord {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+    pub punctuated_word: Option<String>,
+}
+
+/// One Deepgram utterance: a speaker turn or pause-delimited span of the
+/// transcript, used to chapter the `--markdown` output.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedUtterance {
+    pub start: f64,
+    pub end: f64,
+    pub transcript: String,
+}
+
+impl From<&DeepgramResponse> for CachedResponse {
+    fn from(response: &DeepgramResponse) -> Self {
+        let channels = response
+            .results
+            .channels
+            .iter()
+            .map(|channel| CachedChannel {
+                alternatives: channel
+                    .alternatives
+                    .iter()
+                    .map(|alternative| CachedAlternative {
+                        transcript: alternative.transcript.clone(),
+                        words: alternative
+                            .words
+                            .iter()
+                            .map(|word| CachedWord {
+                                word: word.word.clone(),
+                                start: word.start,
+                                end: word.end,
+                                punctuated_word: word
+                                    .punctuated_word
+                                    .clone(),
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        let utterances = response
+            .results
+            .utterances
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|utterance| CachedUtterance {
+                start: utterance.start,
+                end: utterance.end,
+                transcript: utterance.transcript.clone(),
+            })
+            .collect();
+        CachedResponse { channels, utterances }
+    }
+}
+
+/// A lightweight, non-cryptographic content hash (FNV-1a) used to key
+/// cache entries. This only needs to be deterministic across runs, not
+/// collision-resistant, so it's implemented without pulling in a hashing
+/// crate.
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+fn entry_path(cache_dir: &Utf8Path, key: u64) -> Utf8PathBuf {
+    cache_dir.join(format!("{key:016x}.json"))
+}
+
+/// Reads a cached response for `key`, if one exists. Any I/O or parse
+/// failure is treated as a cache miss rather than a hard error, since a
+/// stale or corrupt cache entry shouldn't block transcription.
+pub async fn read(
+    cache_dir: &Utf8Path,
+    key: u64,
+) -> Option<CachedResponse> {
+    let path = entry_path(cache_dir, key);
+    let contents = tokio::fs::read_to_string(&path).await.ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(cached) => {
+            debug!("cache hit at {path}");
+            Some(cached)
+        }
+        Err(error) => {
+            warn!(
+                "ignoring unreadable cache entry at {path}: {error}"
+            );
+            None
+        }
+    }
+}
+
+/// Writes `response` to the cache under `key`, creating `cache_dir` if it
+/// doesn't already exist.
+pub async fn write(
+    cache_dir: &Utf8Path,
+    key: u64,
+    response: &CachedResponse,
+) -> Result<(), CaptionError> {
+    tokio::fs::create_dir_all(cache_dir).await?;
+    let contents = serde_json::to_string(response).map_err(|error| {
+        CaptionError::CacheError { message: error.to_string() }
+    })?;
+    let mut file =
+        tokio::fs::File::create(entry_path(cache_dir, key)).await?;
+    file.write_all(contents.as_bytes()).await?;
+    Ok(())
+}
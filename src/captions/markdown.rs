@@ -0,0 +1,76 @@
+use url::Url;
+
+use super::cache::CachedResponse;
+
+/// Renders a chaptered Markdown transcript, one heading per Deepgram
+/// utterance, prefixed with a `[MM:SS]` timestamp.
+///
+/// When `input` is a YouTube watch URL, each timestamp becomes a deep link
+/// that jumps to that moment in the video; otherwise it's a plain heading.
+pub fn render_markdown(
+    response: &CachedResponse,
+    input: &str,
+) -> String {
+    let youtube_url = youtube_watch_url(input);
+
+    let mut out = String::new();
+    for utterance in &response.utterances {
+        let timestamp = format_timestamp(utterance.start);
+        match &youtube_url {
+            Some(base_url) => out.push_str(&format!(
+                "## [{timestamp}]({})\n\n",
+                deep_link(base_url, utterance.start)
+            )),
+            None => out.push_str(&format!("## [{timestamp}]\n\n")),
+        }
+        out.push_str(utterance.transcript.trim());
+        out.push_str("\n\n");
+    }
+    out
+}
+
+/// If `input` is a YouTube watch URL (`youtube.com/watch?v=...` or the
+/// `youtu.be/...` short form), returns it normalized to the long
+/// `youtube.com/watch` form so [`deep_link`] can append a `t=` parameter.
+fn youtube_watch_url(input: &str) -> Option<Url> {
+    let url = Url::parse(input).ok()?;
+    let host = url.host_str()?;
+
+    if host.ends_with("youtu.be") {
+        let video_id = url.path_segments()?.next()?;
+        return Url::parse(&format!(
+            "https://www.youtube.com/watch?v={video_id}"
+        ))
+        .ok();
+    }
+
+    if host.ends_with("youtube.com")
+        && url.path() == "/watch"
+        && url.query_pairs().any(|(key, _)| key == "v")
+    {
+        return Some(url);
+    }
+
+    None
+}
+
+/// Appends a `t=<seconds>s` deep-link parameter to a YouTube watch URL.
+fn deep_link(base_url: &Url, seconds: f64) -> String {
+    let mut url = base_url.clone();
+    url.query_pairs_mut()
+        .append_pair("t", &format!("{}s", seconds.round() as u64));
+    url.to_string()
+}
+
+/// Formats seconds as `MM:SS`, or `H:MM:SS` once past an hour.
+fn format_timestamp(seconds: f64) -> String {
+    let total_seconds = seconds.round() as u64;
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let secs = total_seconds % 60;
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes:02}:{secs:02}")
+    }
+}
@@ -0,0 +1,81 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use tokio::process::Command;
+use tracing::*;
+
+use super::CaptionError;
+
+/// Probes `input` with ffprobe, and if it contains an audio stream,
+/// transcodes that stream to a temporary 16kHz mono WAV file via ffmpeg.
+///
+/// Returns the path to the temporary file; the caller is responsible for
+/// removing it once done with it.
+pub async fn extract_audio(
+    input: &Utf8Path,
+) -> Result<Utf8PathBuf, CaptionError> {
+    if !has_audio_stream(input).await? {
+        return Err(CaptionError::NoAudioStreamError {
+            filepath: input.to_owned(),
+        });
+    }
+
+    let temp_path = Utf8PathBuf::from(format!(
+        "{}/lamarck-audio-{}.wav",
+        std::env::temp_dir().to_string_lossy(),
+        std::process::id()
+    ));
+
+    debug!("transcoding {input} audio track to {temp_path}");
+
+    let output = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-i")
+        .arg(input.as_str())
+        .arg("-vn")
+        .arg("-ac")
+        .arg("1")
+        .arg("-ar")
+        .arg("16000")
+        .arg("-f")
+        .arg("wav")
+        .arg(temp_path.as_str())
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(CaptionError::FfmpegError {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr)
+                .into_owned(),
+        });
+    }
+
+    Ok(temp_path)
+}
+
+/// Checks whether `input` has at least one audio stream, via ffprobe.
+async fn has_audio_stream(
+    input: &Utf8Path,
+) -> Result<bool, CaptionError> {
+    let output = Command::new("ffprobe")
+        .arg("-v")
+        .arg("error")
+        .arg("-select_streams")
+        .arg("a")
+        .arg("-show_entries")
+        .arg("stream=index")
+        .arg("-of")
+        .arg("csv=p=0")
+        .arg(input.as_str())
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(CaptionError::FfmpegError {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr)
+                .into_owned(),
+        });
+    }
+
+    Ok(!output.stdout.is_empty())
+}
@@ -0,0 +1,103 @@
+use super::cache::CachedResponse;
+use super::srt::{words_to_cues, Cue};
+
+/// Scenarist-style CEA-608 Closed Caption (`.scc`) transcripts, one per
+/// channel/alternative.
+///
+/// This models "pop-on" captioning: each cue clears the displayed memory,
+/// resumes caption loading into the non-displayed buffer, positions it
+/// with a row-15 preamble address code, then ends the caption to swap the
+/// buffers, doubling every code pair for redundancy as SCC conventionally
+/// does. All bytes, including the control codes, carry the odd parity bit
+/// CEA-608 requires. Only the basic Latin character subset is modeled;
+/// anything outside printable ASCII falls back to a space rather than the
+/// full extended character set.
+pub struct Scc {
+    pub channels: Vec<Vec<String>>,
+}
+
+impl From<&CachedResponse> for Scc {
+    fn from(response: &CachedResponse) -> Self {
+        let channels = response
+            .channels
+            .iter()
+            .map(|channel| {
+                channel
+                    .alternatives
+                    .iter()
+                    .map(|alternative| {
+                        render_scc(&words_to_cues(&alternative.words))
+                    })
+                    .collect()
+            })
+            .collect();
+        Scc { channels }
+    }
+}
+
+const ERASE_DISPLAYED_MEMORY: &str = "942c 942c";
+const RESUME_CAPTION_LOADING: &str = "9420 9420";
+/// Preamble address code: row 15 (bottom row), white, no indent.
+const PAC_ROW_15_WHITE: &str = "94e0 94e0";
+const END_OF_CAPTION: &str = "942f 942f";
+
+fn render_scc(cues: &[Cue]) -> String {
+    let mut out = String::from("Scenarist_SCC V1.0\n\n");
+    for cue in cues {
+        out.push_str(&format!(
+            "{}\t{ERASE_DISPLAYED_MEMORY} {RESUME_CAPTION_LOADING} {PAC_ROW_15_WHITE} {} {END_OF_CAPTION}\n\n",
+            format_timecode(cue.start),
+            encode_text(&cue.text),
+        ));
+    }
+    out
+}
+
+/// Packs printable ASCII text into odd-parity SCC byte-pairs, two
+/// characters per 4-hex-digit code, padding an odd trailing character
+/// with a space.
+fn encode_text(text: &str) -> String {
+    let bytes: Vec<u8> = text
+        .chars()
+        .map(|character| {
+            if character.is_ascii_graphic() || character == ' ' {
+                character as u8
+            } else {
+                b' '
+            }
+        })
+        .map(odd_parity)
+        .collect();
+
+    bytes
+        .chunks(2)
+        .map(|chunk| {
+            let first = chunk[0];
+            let second = chunk.get(1).copied().unwrap_or(odd_parity(b' '));
+            format!("{first:02x}{second:02x}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Sets CEA-608's parity bit (the high bit) so the byte always has an odd
+/// number of set bits, as every transmitted byte requires.
+fn odd_parity(byte: u8) -> u8 {
+    let data = byte & 0x7f;
+    if data.count_ones() % 2 == 0 {
+        data | 0x80
+    } else {
+        data
+    }
+}
+
+/// Formats seconds as an SCC timecode: `HH:MM:SS:FF` at 30fps (non-drop-frame).
+fn format_timecode(seconds: f64) -> String {
+    let total_frames = (seconds * 30.0).round() as u64;
+    let frames = total_frames % 30;
+    let total_seconds = total_frames / 30;
+    let secs = total_seconds % 60;
+    let minutes = (total_seconds / 60) % 60;
+    let hours = total_seconds / 3600;
+    format!("{hours:02}:{minutes:02}:{secs:02}:{frames:02}")
+}
@@ -0,0 +1,197 @@
+use super::cache::{CachedAlternative, CachedResponse, CachedWord};
+
+/// SRT-formatted transcripts, one rendered file per channel/alternative.
+///
+/// Indexing mirrors Deepgram's response shape: `channels[channel_id][alternative_id]`
+/// holds the full `.srt` file contents for that alternative.
+pub struct Srt {
+    pub channels: Vec<Vec<String>>,
+}
+
+impl From<&CachedResponse> for Srt {
+    fn from(response: &CachedResponse) -> Self {
+        let channels = response
+            .channels
+            .iter()
+            .map(|channel| {
+                channel
+                    .alternatives
+                    .iter()
+                    .map(alternative_to_srt)
+                    .collect()
+            })
+            .collect();
+        Srt { channels }
+    }
+}
+
+fn alternative_to_srt(alternative: &CachedAlternative) -> String {
+    render_cues(&words_to_cues(&alternative.words))
+}
+
+/// Builds SRT transcripts like [`Srt::from`], but word-wraps each cue to
+/// `max_chars_per_line` characters and splits any cue that still overflows
+/// `max_lines` into multiple consecutive cues, dividing the original time
+/// span proportionally by word count.
+pub fn wrapped(
+    response: &CachedResponse,
+    max_chars_per_line: usize,
+    max_lines: usize,
+) -> Srt {
+    let channels = response
+        .channels
+        .iter()
+        .map(|channel| {
+            channel
+                .alternatives
+                .iter()
+                .map(|alternative| {
+                    let cues = words_to_cues(&alternative.words);
+                    let wrapped =
+                        wrap_cues(cues, max_chars_per_line, max_lines);
+                    render_cues(&wrapped)
+                })
+                .collect()
+        })
+        .collect();
+    Srt { channels }
+}
+
+fn wrap_cues(
+    cues: Vec<Cue>,
+    max_chars_per_line: usize,
+    max_lines: usize,
+) -> Vec<Cue> {
+    cues
+        .into_iter()
+        .flat_map(|cue| wrap_cue(cue, max_chars_per_line, max_lines))
+        .collect()
+}
+
+fn wrap_cue(
+    cue: Cue,
+    max_chars_per_line: usize,
+    max_lines: usize,
+) -> Vec<Cue> {
+    let lines = wrap_text(&cue.text, max_chars_per_line);
+    if lines.len() <= max_lines {
+        return vec![Cue {
+            start: cue.start,
+            end: cue.end,
+            text: lines.join("\n"),
+        }];
+    }
+
+    let total_words = cue.text.split_whitespace().count();
+    if total_words == 0 {
+        return vec![cue];
+    }
+    let duration = cue.end - cue.start;
+
+    let mut sub_cues = Vec::new();
+    let mut elapsed_words = 0;
+    let mut cursor = cue.start;
+    let chunks: Vec<_> = lines.chunks(max_lines).collect();
+    let last_chunk_index = chunks.len() - 1;
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let text = chunk.join("\n");
+        elapsed_words += text.split_whitespace().count();
+        let end = if index == last_chunk_index {
+            cue.end
+        } else {
+            cue.start
+                + duration * (elapsed_words as f64 / total_words as f64)
+        };
+        sub_cues.push(Cue { start: cursor, end, text });
+        cursor = end;
+    }
+    sub_cues
+}
+
+/// Greedily word-wraps `text` into lines no longer than
+/// `max_chars_per_line` characters. A single token longer than the limit
+/// is emitted on its own line rather than looping forever.
+fn wrap_text(text: &str, max_chars_per_line: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        if current.is_empty() {
+            current.push_str(word);
+        } else if current.chars().count() + 1 + word.chars().count()
+            <= max_chars_per_line
+        {
+            current.push(' ');
+            current.push_str(word);
+        } else {
+            lines.push(std::mem::take(&mut current));
+            current.push_str(word);
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+/// A single subtitle cue: a time span and the text spoken during it.
+pub(crate) struct Cue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// Groups words into sentence-sized cues, splitting after sentence-ending
+/// punctuation so each cue reads like a caption instead of a wall of text.
+pub(crate) fn words_to_cues(words: &[CachedWord]) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut current: Vec<&CachedWord> = Vec::new();
+
+    for word in words {
+        current.push(word);
+        let text = word.punctuated_word.as_deref().unwrap_or(&word.word);
+        if text.ends_with(['.', '?', '!']) {
+            cues.push(cue_from_words(&current));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        cues.push(cue_from_words(&current));
+    }
+    cues
+}
+
+fn cue_from_words(words: &[&CachedWord]) -> Cue {
+    let start = words.first().map(|word| word.start).unwrap_or(0.0);
+    let end = words.last().map(|word| word.end).unwrap_or(start);
+    let text = words
+        .iter()
+        .map(|word| word.punctuated_word.as_deref().unwrap_or(&word.word))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Cue { start, end, text }
+}
+
+pub(crate) fn render_cues(cues: &[Cue]) -> String {
+    let mut out = String::new();
+    for (index, cue) in cues.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(cue.start),
+            format_timestamp(cue.end),
+            cue.text
+        ));
+    }
+    out
+}
+
+/// Formats seconds as an SRT timestamp: `HH:MM:SS,mmm`.
+pub(crate) fn format_timestamp(seconds: f64) -> String {
+    let millis_total = (seconds * 1000.0).round() as u64;
+    let hours = millis_total / 3_600_000;
+    let minutes = (millis_total % 3_600_000) / 60_000;
+    let secs = (millis_total % 60_000) / 1_000;
+    let millis = millis_total % 1_000;
+    format!("{hours:02}:{minutes:02}:{secs:02},{millis:03}")
+}
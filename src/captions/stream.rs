@@ -0,0 +1,190 @@
+use camino::Utf8PathBuf;
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::fs::File;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::*;
+
+use super::CaptionError;
+
+const LIVE_ENDPOINT: &str = "wss://api.deepgram.com/v1/listen";
+
+/// Builds the live-transcription URL, telling Deepgram how to interpret the
+/// containerless PCM we stream to it.
+///
+/// Deepgram can't infer `encoding`/`sample_rate`/`channels` from raw
+/// linear16 bytes the way it can from a self-describing container, so
+/// omitting them leaves the audio undecodable and no finals are ever sent
+/// back.
+fn live_endpoint_url(sample_rate: u32, channels: u32) -> String {
+    format!(
+        "{LIVE_ENDPOINT}?encoding=linear16&sample_rate={sample_rate}&channels={channels}"
+    )
+}
+
+/// How many raw PCM bytes to read per WebSocket frame.
+///
+/// Deepgram recommends sending 20-100ms worth of audio per message; at
+/// 16kHz/16-bit/mono that's 640-3200 bytes.
+const CHUNK_BYTES: usize = 3200;
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type")]
+enum LiveMessage {
+    Results(LiveResults),
+    Metadata(serde_json::Value),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+struct LiveResults {
+    is_final: bool,
+    channel: LiveChannel,
+}
+
+#[derive(Deserialize, Debug)]
+struct LiveChannel {
+    alternatives: Vec<LiveAlternative>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LiveAlternative {
+    transcript: String,
+    words: Vec<LiveWord>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LiveWord {
+    word: String,
+    start: f64,
+    end: f64,
+    punctuated_word: Option<String>,
+}
+
+/// Opens Deepgram's live transcription WebSocket and streams audio read
+/// from `input` to it, writing finalized cues to `output_path` as an SRT
+/// file as they arrive.
+///
+/// `input` is read in small chunks as raw linear16/PCM at `sample_rate`
+/// and `channels` and forwarded as binary WebSocket messages; a
+/// `CloseStream` control message is sent once the input is exhausted.
+/// Interim (`is_final: false`) results are logged but not written, since
+/// they can still change.
+pub async fn stream_captions(
+    api_key: &str,
+    input: &mut (impl AsyncRead + Unpin),
+    output_path: &Utf8PathBuf,
+    sample_rate: u32,
+    channels: u32,
+) -> Result<(), CaptionError> {
+    let mut request =
+        live_endpoint_url(sample_rate, channels).into_client_request()?;
+    let auth_value = format!("Token {api_key}")
+        .parse()
+        .map_err(|error| CaptionError::StreamSetupError {
+            message: format!("invalid API key header value: {error}"),
+        })?;
+    request
+        .headers_mut()
+        .insert(AUTHORIZATION, auth_value);
+
+    let (ws_stream, _) =
+        tokio_tungstenite::connect_async(request).await?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let mut output = File::create(output_path).await?;
+    let mut cue_index: usize = 1;
+
+    let send_audio = async {
+        let mut buf = vec![0u8; CHUNK_BYTES];
+        loop {
+            let read_bytes = input.read(&mut buf).await?;
+            if read_bytes == 0 {
+                break;
+            }
+            write
+                .send(Message::Binary(buf[..read_bytes].to_vec()))
+                .await?;
+        }
+        write
+            .send(Message::Text(
+                r#"{"type": "CloseStream"}"#.to_string(),
+            ))
+            .await?;
+        Ok::<(), CaptionError>(())
+    };
+
+    let receive_results = async {
+        while let Some(message) = read.next().await {
+            let message = message?;
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+
+            let parsed: LiveMessage =
+                match serde_json::from_str(&text) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        debug!(
+                            "ignoring unparsable live message: {error}"
+                        );
+                        continue;
+                    }
+                };
+
+            let results = match parsed {
+                LiveMessage::Results(results) => results,
+                _ => continue,
+            };
+
+            let Some(alternative) =
+                results.channel.alternatives.first()
+            else {
+                continue;
+            };
+
+            if !results.is_final || alternative.words.is_empty() {
+                debug!(
+                    "interim result: {:?}",
+                    alternative.transcript
+                );
+                continue;
+            }
+
+            let start = alternative.words.first().unwrap().start;
+            let end = alternative.words.last().unwrap().end;
+            let text = alternative
+                .words
+                .iter()
+                .map(|word| {
+                    word.punctuated_word
+                        .as_deref()
+                        .unwrap_or(&word.word)
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let cue = format!(
+                "{}\n{} --> {}\n{}\n\n",
+                cue_index,
+                super::srt::format_timestamp(start),
+                super::srt::format_timestamp(end),
+                text
+            );
+            output.write_all(cue.as_bytes()).await?;
+            output.flush().await?;
+            cue_index += 1;
+        }
+        Ok::<(), CaptionError>(())
+    };
+
+    tokio::try_join!(send_audio, receive_results)?;
+
+    Ok(())
+}
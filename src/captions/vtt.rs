@@ -0,0 +1,53 @@
+use super::cache::{CachedAlternative, CachedResponse};
+use super::srt::{words_to_cues, Cue};
+
+/// WebVTT transcripts, one rendered file per channel/alternative. Unlike
+/// SRT, WebVTT unlocks `<track>` elements in web video players.
+pub struct Vtt {
+    pub channels: Vec<Vec<String>>,
+}
+
+impl From<&CachedResponse> for Vtt {
+    fn from(response: &CachedResponse) -> Self {
+        let channels = response
+            .channels
+            .iter()
+            .map(|channel| {
+                channel
+                    .alternatives
+                    .iter()
+                    .map(alternative_to_vtt)
+                    .collect()
+            })
+            .collect();
+        Vtt { channels }
+    }
+}
+
+fn alternative_to_vtt(alternative: &CachedAlternative) -> String {
+    render_cues(&words_to_cues(&alternative.words))
+}
+
+fn render_cues(cues: &[Cue]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for (index, cue) in cues.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {} line:90%\n{}\n\n",
+            index + 1,
+            format_timestamp(cue.start),
+            format_timestamp(cue.end),
+            cue.text
+        ));
+    }
+    out
+}
+
+/// Formats seconds as a WebVTT timestamp: `HH:MM:SS.mmm`.
+fn format_timestamp(seconds: f64) -> String {
+    let millis_total = (seconds * 1000.0).round() as u64;
+    let hours = millis_total / 3_600_000;
+    let minutes = (millis_total % 3_600_000) / 60_000;
+    let secs = (millis_total % 60_000) / 1_000;
+    let millis = millis_total % 1_000;
+    format!("{hours:02}:{minutes:02}:{secs:02}.{millis:03}")
+}